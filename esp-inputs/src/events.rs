@@ -1,7 +1,11 @@
+// `On`/`Off` come from Switch and Analog inputs. Button inputs are always routed through
+// `Input::tick_button`, which only ever produces `Click`/`DoubleClick`/`LongPress`/`Hold`.
 #[derive(Debug, Copy, Clone)]
 pub enum Event {
     On(i32),
     Off(i32),
-    Pressed(i32),
-    Released(i32),
+    Click(i32),
+    DoubleClick(i32),
+    LongPress(i32),
+    Hold(i32),
 }
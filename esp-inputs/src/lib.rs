@@ -1,5 +1,5 @@
 use esp_idf_svc::hal::{
-    gpio::{AnyIOPin, Input as MODE_Input, InterruptType, Level, PinDriver, Pull},
+    gpio::{AnyIOPin, Input as MODE_Input, InterruptType, Level, Pin, PinDriver, Pull},
     sys::EspError,
 };
 use log::{debug, error};
@@ -8,15 +8,37 @@ use std::collections::HashMap;
 mod events;
 mod irq;
 
+// Async variant built on embassy-executor/embassy-time, for the embedded-hal-async stack. Opt in
+// with the `embassy` feature - it replaces the IRQ queue and polling timer in this module with a
+// per-input task and a channel, see the module doc for how the two compare.
+#[cfg(feature = "embassy")]
+pub mod embassy;
+
 pub use events::Event;
 use irq::InterruptHandler;
 
+// Something that can be sampled for a millivolt reading. Implement this for an ESP ADC oneshot
+// channel (`AdcChannelDriver`) to wire a potentiometer, photoresistor, or voltage rail up to
+// `InputManager::new_analog` and get a debounced `Event` stream out of it, the same as a switch.
+pub trait AnalogChannel {
+    fn read_mv(&mut self) -> Result<u16, EspError>;
+}
+
 // The number of samples to take when debouncing an input. When an input changes, an interrupt is
 // fired. That interrupt is then cleared and checked during the input loop. It is quite likely that
 // the input loop and the interrupt don't happen at the same time (e.g. all samples should be the
 // same), however this provides a gaurantee of signal stability.
 const SAMPLES: usize = 5;
 
+// Approximate period between `InputManager::events()` calls, used to turn tick counts into
+// wall-clock durations for gesture timing. Matches the 2 ms polling timer in main.rs.
+const TICK_MS: u32 = 2;
+
+const CLICK_MAX_TICKS: u32 = 500 / TICK_MS;
+const DOUBLE_CLICK_WINDOW_TICKS: u32 = 300 / TICK_MS;
+const LONG_PRESS_TICKS: u32 = 1000 / TICK_MS;
+const HOLD_REPEAT_TICKS: u32 = 250 / TICK_MS;
+
 // Help manage multiple inputs using interrupts that are debounced.
 pub struct InputManager<'d> {
     inputs: HashMap<i32, Input<'d>>,
@@ -54,13 +76,31 @@ impl<'d> InputManager<'d> {
         self.register_input(pin, InputMode::Switch, with_interrupts)
     }
 
-    // Helper function to register a button input
-    // TODO: Support "Click" and "Double Click" events
+    // Helper function to register a button input. Emits Click/DoubleClick/LongPress/Hold
+    // gesture events, never On/Off (see `Input::tick_button`).
     #[allow(dead_code)]
     pub fn new_button(&mut self, pin: AnyIOPin, with_interrupts: bool) -> Result<(), EspError> {
         self.register_input(pin, InputMode::Button, with_interrupts)
     }
 
+    // Helper function to register an analog input, read via hysteresis comparator
+    //
+    // There's no edge interrupt for an analog pin, so unlike the digital inputs this one is
+    // sampled once per `events()` tick. The logical state only goes High once the reading rises
+    // above `high_mv`, and only returns Low once it falls below `low_mv` - the dead band between
+    // the two suppresses flicker from a noisy reading sitting near a single threshold.
+    #[allow(dead_code)]
+    pub fn new_analog(
+        &mut self,
+        pin: AnyIOPin,
+        channel: impl AnalogChannel + 'd,
+        low_mv: u16,
+        high_mv: u16,
+    ) {
+        let input = Input::new_analog(pin.pin(), channel, low_mv, high_mv);
+        self.inputs.insert(input.pin, input);
+    }
+
     // Evalute the state of all inputs
     pub fn events(&mut self) -> Vec<Event> {
         let mut dequeued = 0;
@@ -91,15 +131,65 @@ impl<'d> InputManager<'d> {
 pub enum InputMode {
     Switch,
     Button,
+    Analog,
+}
+
+// The digital pin or analog channel backing an `Input`
+enum Source<'d> {
+    Digital {
+        input: PinDriver<'d, AnyIOPin, MODE_Input>,
+        // Integrate-and-dump debounce counter, clamped to [0, 2*SAMPLES]. Reset to the midpoint
+        // (SAMPLES) on every commit, so a freshly-dirtied input needs at least SAMPLES ticks to
+        // commit a new state (exactly SAMPLES when every read agrees), giving a `SAMPLES *
+        // tick_period` debounce time for a clean edge.
+        counter: i32,
+    },
+    Analog {
+        channel: Box<dyn AnalogChannel + 'd>,
+        low_mv: u16,
+        high_mv: u16,
+    },
+}
+
+// Where a Button input sits in the click / double-click / long-press / hold state machine
+#[derive(Clone, Copy)]
+enum GestureState {
+    Idle,
+    Pressed,
+    LongPress,
+    // Released once; waiting to see if a second click arrives before the double-click window closes
+    AwaitingDoubleClick,
+}
+
+// Tick-counted timing state for a Button input's gesture detection. Only carried by inputs
+// registered in Button mode - mirrors `press_start_tick`/`last_release_tick`/`gesture_state`
+// driven off the same tick cadence as `InputManager::events()`.
+struct ButtonGesture {
+    tick: u32,
+    press_start_tick: Option<u32>,
+    last_release_tick: Option<u32>,
+    gesture_state: GestureState,
+}
+
+impl ButtonGesture {
+    fn new() -> Self {
+        Self {
+            tick: 0,
+            press_start_tick: None,
+            last_release_tick: None,
+            gesture_state: GestureState::Idle,
+        }
+    }
 }
 
 pub struct Input<'d> {
     pub state: Level,
-    input: PinDriver<'d, AnyIOPin, MODE_Input>,
+    source: Source<'d>,
     pub pin: i32,
     pub dirty: bool,
     has_interrupts: bool,
     mode: InputMode,
+    gesture: Option<ButtonGesture>,
 }
 
 impl<'d> Input<'d> {
@@ -108,93 +198,256 @@ impl<'d> Input<'d> {
         let mut input = PinDriver::input(pin)?;
         let pin = input.pin();
         input.set_pull(Pull::Up)?;
+        let state = input.get_level();
+        let gesture = matches!(mode, InputMode::Button).then(ButtonGesture::new);
         Ok(Self {
-            state: input.get_level(),
-            input,
+            state,
+            source: Source::Digital {
+                input,
+                counter: SAMPLES as i32,
+            },
             pin,
             dirty: false,
             has_interrupts: false,
             mode,
+            gesture,
         })
     }
 
+    // Generate a new analog input, sampled through a hysteresis comparator
+    fn new_analog(pin: i32, channel: impl AnalogChannel + 'd, low_mv: u16, high_mv: u16) -> Self {
+        Self {
+            state: Level::Low,
+            source: Source::Analog {
+                channel: Box::new(channel),
+                low_mv,
+                high_mv,
+            },
+            pin,
+            dirty: false,
+            has_interrupts: false,
+            mode: InputMode::Analog,
+            gesture: None,
+        }
+    }
+
     // Register an interrupt handler for the input
     //
     // Note: this function is required at present since polling is not supported (yet)
     pub fn with_interrupts(mut self, handler: &mut InterruptHandler) -> Result<Self, EspError> {
+        let Source::Digital { input, .. } = &mut self.source else {
+            error!("Analog inputs don't support interrupts, ignoring");
+            return Ok(self);
+        };
         self.has_interrupts = true;
         // Setup the input pin
-        self.input.set_interrupt_type(InterruptType::AnyEdge)?;
-        unsafe { self.input.subscribe(handler.register(self.pin))? };
-        self.input.enable_interrupt()?;
+        input.set_interrupt_type(InterruptType::AnyEdge)?;
+        unsafe { input.subscribe(handler.register(self.pin))? };
+        input.enable_interrupt()?;
 
         Ok(self)
     }
 
+    // Mark the input dirty so the next `events()` tick starts (or continues) debouncing it.
+    //
+    // This does not sample the pin - sampling happens once per tick in `sample_digital` so a
+    // flickering or floating pin can never block the interrupt handler.
     fn handle_interrupt(&mut self) -> Result<(), EspError> {
         if !self.has_interrupts {
             error!("Handling unregistered interrupt");
             // TODO: should be an error
             return Ok(());
         }
-        // if we have an interrupt, we need to check the state of the input
         self.dirty = true;
-        self.debounce();
-        self.input.enable_interrupt()
+        let Source::Digital { input, .. } = &mut self.source else {
+            return Ok(());
+        };
+        input.enable_interrupt()
     }
 
-    // Debounce the input
+    // Sample the digital pin once and feed it into an integrate-and-dump debounce counter.
     //
-    // This function will debounce the input signal by ensuring that a signal has a constant level
-    // for at least `SAMPLES` length. This is achieved in a O(1) memory space by starting a count
-    // at `SAMPLES`, counting a HIGH as +1 and a LOW as -1. When the count reaches 0 or 2*SAMPLES,
-    // then the signal should be stable for at least `SAMPLES` count.
+    // Each call adds 1 to the counter on a High read and subtracts 1 on a Low read, clamped to
+    // `[0, 2*SAMPLES]`. Once the counter saturates at a bound the signal has read that level
+    // `SAMPLES` more times than the other since the last commit, so the counter resets to the
+    // midpoint and the new level is committed. This replaces the old busy-loop over `get_level()`: debounce time is now spread
+    // across real `events()` ticks instead of a handful of back-to-back samples, and it can never
+    // block since each tick only takes one sample.
     //
-    // Warning: This function will indefinitely block if the signal is never stable (e.g.
-    // floating). Ensure a pull-up or pull-down is set on the input
-    fn debounce(&mut self) {
-        let mut level = self.input.get_level();
-        let mut count = SAMPLES;
-        while count != 0 && count < SAMPLES * 2 {
-            count = if level == Level::High {
-                count.saturating_add(1)
-            } else {
-                count.saturating_sub(1)
-            };
-            level = self.input.get_level();
+    // Returns `Some(level)` only on a tick that commits a *different* level than before (i.e. an
+    // actual transition); `None` while still settling or once settled back to the prior level.
+    fn sample_digital(&mut self) -> Option<Level> {
+        let Source::Digital { input, counter } = &mut self.source else {
+            return None;
+        };
+
+        *counter = (*counter + if input.get_level() == Level::High { 1 } else { -1 })
+            .clamp(0, SAMPLES as i32 * 2);
+
+        let committed = match *counter {
+            0 => Level::Low,
+            n if n == SAMPLES as i32 * 2 => Level::High,
+            _ => return None,
+        };
+
+        *counter = SAMPLES as i32;
+        self.dirty = false;
+        (committed != self.state).then_some(committed)
+    }
+
+    // Sample the analog channel through the hysteresis comparator, returning the new level if
+    // the reading crossed the threshold away from the current state
+    fn sample_analog(&mut self) -> Option<Level> {
+        let Source::Analog {
+            channel,
+            low_mv,
+            high_mv,
+        } = &mut self.source
+        else {
+            return None;
+        };
+        let mv = match channel.read_mv() {
+            Ok(mv) => mv,
+            Err(e) => {
+                error!("Failed to read analog channel on pin {}: {}", self.pin, e);
+                return None;
+            }
+        };
+        match self.state {
+            Level::Low if mv > *high_mv => Some(Level::High),
+            Level::High if mv < *low_mv => Some(Level::Low),
+            _ => None,
         }
-        self.state = if count == 0 { Level::Low } else { Level::High };
     }
 
     // Evalute the state of the input, returning an input event if applicable.
     //
-    // The state of the switch is debounced by taking a series of samples until
-    // the window of samples are all the same value. The state is determined by
-    // the final value of all samples combined (they need to be unanimous).
+    // Digital inputs debounce via `sample_digital` (see its doc comment); analog inputs are
+    // sampled every tick through a hysteresis comparator in `sample_analog`.
     //
     // Returns:
     // - None when nothing has changed
     // - Some(Event) based on the new state if it was changed
     fn tick(&mut self) -> Option<Event> {
-        if !self.dirty {
-            return None;
+        if self.gesture.is_some() {
+            return self.tick_button();
+        }
+
+        match &self.source {
+            Source::Digital { .. } => {
+                if !self.dirty {
+                    return None;
+                }
+                self.state = self.sample_digital()?;
+            }
+            Source::Analog { .. } => {
+                self.state = self.sample_analog()?;
+            }
         }
 
-        self.dirty = false;
         Some(self.input_event())
     }
 
+    // Drive the click / double-click / long-press / hold state machine for a Button input.
+    //
+    // Runs every tick (not gated on `dirty`) since a held button needs to cross the long-press
+    // and hold thresholds without a fresh edge, and a pending click needs to time out the
+    // double-click window even if the button stays released.
+    fn tick_button(&mut self) -> Option<Event> {
+        if self.dirty {
+            if let Some(new_state) = self.sample_digital() {
+                self.state = new_state;
+            }
+        }
+
+        let pin = self.pin;
+        let pressed = self.state == Level::High;
+        let gesture = self.gesture.as_mut().expect("tick_button requires gesture state");
+        gesture.tick = gesture.tick.wrapping_add(1);
+        let tick = gesture.tick;
+
+        match gesture.gesture_state {
+            GestureState::Idle => {
+                if pressed {
+                    gesture.press_start_tick = Some(tick);
+                    gesture.gesture_state = GestureState::Pressed;
+                }
+                None
+            }
+            GestureState::Pressed => {
+                let held_since = gesture.press_start_tick.unwrap_or(tick);
+                if pressed {
+                    if tick.wrapping_sub(held_since) >= LONG_PRESS_TICKS {
+                        gesture.gesture_state = GestureState::LongPress;
+                        return Some(Event::LongPress(pin));
+                    }
+                    return None;
+                }
+
+                gesture.press_start_tick = None;
+                if tick.wrapping_sub(held_since) > CLICK_MAX_TICKS {
+                    // held too long to count as a click, but released before the long-press
+                    // threshold: not a gesture we report
+                    gesture.gesture_state = GestureState::Idle;
+                    gesture.last_release_tick = None;
+                    return None;
+                }
+
+                let is_double_click = gesture
+                    .last_release_tick
+                    .is_some_and(|last| tick.wrapping_sub(last) <= DOUBLE_CLICK_WINDOW_TICKS);
+                gesture.last_release_tick = Some(tick);
+                if is_double_click {
+                    gesture.gesture_state = GestureState::Idle;
+                    gesture.last_release_tick = None;
+                    Some(Event::DoubleClick(pin))
+                } else {
+                    gesture.gesture_state = GestureState::AwaitingDoubleClick;
+                    None
+                }
+            }
+            GestureState::LongPress => {
+                if pressed {
+                    let held_since = gesture.press_start_tick.unwrap_or(tick);
+                    if tick.wrapping_sub(held_since).wrapping_sub(LONG_PRESS_TICKS) % HOLD_REPEAT_TICKS == 0 {
+                        return Some(Event::Hold(pin));
+                    }
+                    return None;
+                }
+
+                gesture.gesture_state = GestureState::Idle;
+                gesture.press_start_tick = None;
+                gesture.last_release_tick = None;
+                None
+            }
+            GestureState::AwaitingDoubleClick => {
+                if pressed {
+                    gesture.press_start_tick = Some(tick);
+                    gesture.gesture_state = GestureState::Pressed;
+                    return None;
+                }
+
+                let last = gesture.last_release_tick.unwrap_or(tick);
+                if tick.wrapping_sub(last) > DOUBLE_CLICK_WINDOW_TICKS {
+                    gesture.gesture_state = GestureState::Idle;
+                    gesture.last_release_tick = None;
+                    return Some(Event::Click(pin));
+                }
+                None
+            }
+        }
+    }
+
+    // Only reachable for Switch/Analog inputs - Button inputs are always routed through
+    // `tick_button` instead, since they carry `gesture` state.
     fn input_event(&self) -> Event {
         let pin = self.pin;
         match self.mode {
-            InputMode::Switch => match self.state {
+            InputMode::Switch | InputMode::Analog => match self.state {
                 Level::High => Event::On(pin),
                 Level::Low => Event::Off(pin),
             },
-            InputMode::Button => match self.state {
-                Level::High => Event::Pressed(pin),
-                Level::Low => Event::Released(pin),
-            },
+            InputMode::Button => unreachable!("button inputs are handled by tick_button"),
         }
     }
 }
@@ -0,0 +1,96 @@
+use esp_idf_svc::eventloop::EspSystemEventLoop;
+use esp_idf_svc::espnow::{EspNow, PeerInfo, BROADCAST};
+use esp_idf_svc::hal::delay;
+use esp_idf_svc::sys::EspError;
+use esp_inputs::Event as InputEvent;
+use log::{error, warn};
+
+use crate::events::Event;
+
+// ESP-NOW frames are an explicit tag + i32 payload, not a bitwise copy of `Event` - `Event` has no
+// `#[repr(...)]`, and ESP-NOW broadcasts are unauthenticated/unencrypted (see `PeerInfo` below), so
+// `decode` has to be able to reject a corrupted or foreign frame rather than reinterpret arbitrary
+// bytes as an `Event`.
+const FRAME_LEN: usize = 1 + 4;
+
+const TAG_DISPLAY_FRAME: u8 = 0;
+const TAG_ON: u8 = 1;
+const TAG_OFF: u8 = 2;
+const TAG_CLICK: u8 = 3;
+const TAG_DOUBLE_CLICK: u8 = 4;
+const TAG_LONG_PRESS: u8 = 5;
+const TAG_HOLD: u8 = 6;
+
+// Broadcasts local `Event`s to every other node over ESP-NOW, and reposts whatever it receives
+// onto the local `EspSystemEventLoop` as the same `Event` it was sent as, so a switch flipped on one board
+// drives every board's `LedDisplay` the same way. Requires Wi-Fi to already be started (ESP-NOW
+// rides on the Wi-Fi radio even though it never associates with an AP).
+pub struct EspNowTransport {
+    esp_now: EspNow<'static>,
+}
+
+impl EspNowTransport {
+    pub fn new(sys_loop: EspSystemEventLoop) -> Result<Self, EspError> {
+        let esp_now = EspNow::take()?;
+        esp_now.add_peer(PeerInfo {
+            peer_addr: BROADCAST,
+            ..Default::default()
+        })?;
+
+        esp_now.register_recv_cb(move |_mac, data| match Self::decode(data) {
+            Some(event) => {
+                if let Err(e) = sys_loop.post::<Event>(&event, delay::BLOCK) {
+                    error!("Failed to repost ESP-NOW frame locally: {:?}", e);
+                }
+            }
+            None => warn!("Dropping malformed ESP-NOW frame ({} bytes)", data.len()),
+        })?;
+
+        Ok(Self { esp_now })
+    }
+
+    // Broadcast a local event to every peer on the network
+    pub fn send(&self, event: Event) -> Result<(), EspError> {
+        self.esp_now.send(BROADCAST, &Self::encode(&event))
+    }
+
+    fn encode(event: &Event) -> [u8; FRAME_LEN] {
+        let (tag, pin) = match event {
+            Event::DisplayFrame => (TAG_DISPLAY_FRAME, 0),
+            Event::Input(InputEvent::On(pin)) => (TAG_ON, *pin),
+            Event::Input(InputEvent::Off(pin)) => (TAG_OFF, *pin),
+            Event::Input(InputEvent::Click(pin)) => (TAG_CLICK, *pin),
+            Event::Input(InputEvent::DoubleClick(pin)) => (TAG_DOUBLE_CLICK, *pin),
+            Event::Input(InputEvent::LongPress(pin)) => (TAG_LONG_PRESS, *pin),
+            Event::Input(InputEvent::Hold(pin)) => (TAG_HOLD, *pin),
+        };
+
+        let mut frame = [0u8; FRAME_LEN];
+        frame[0] = tag;
+        frame[1..].copy_from_slice(&pin.to_le_bytes());
+        frame
+    }
+
+    fn decode(data: &[u8]) -> Option<Event> {
+        if data.len() != FRAME_LEN {
+            return None;
+        }
+
+        let tag = data[0];
+        if tag == TAG_DISPLAY_FRAME {
+            return Some(Event::DisplayFrame);
+        }
+
+        let pin = i32::from_le_bytes(data[1..FRAME_LEN].try_into().ok()?);
+        let input_event = match tag {
+            TAG_ON => InputEvent::On(pin),
+            TAG_OFF => InputEvent::Off(pin),
+            TAG_CLICK => InputEvent::Click(pin),
+            TAG_DOUBLE_CLICK => InputEvent::DoubleClick(pin),
+            TAG_LONG_PRESS => InputEvent::LongPress(pin),
+            TAG_HOLD => InputEvent::Hold(pin),
+            _ => return None,
+        };
+        Some(Event::Input(input_event))
+    }
+}
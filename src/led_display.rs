@@ -4,25 +4,33 @@ use esp_idf_svc::{
     hal::{delay, sys::EspError},
     timer::{EspTaskTimerService, EspTimer},
 };
-use smart_leds::hsv::{hsv2rgb, Hsv};
+use smart_leds::RGB8;
 use smart_leds_trait::SmartLedsWrite; // Required for ws2812.write()
 use ws2812_esp32_rmt_driver::{Ws2812Esp32Rmt, Ws2812Esp32RmtDriverError};
 
 use crate::events::Event;
+use crate::led_patterns::{Cylon, LedPattern, Oscillator, Rainbow, Solid, Twinkle};
 
-const FRAME_RATE: u32 = 60;
+// Fixed rotation cycled through by `LedDisplay::next_pattern`
+const PATTERN_COUNT: usize = 5;
 
-const OSCILLATOR_SPACE: f64 = std::f64::consts::PI * 2.0;
-const OSCILLATOR_HZ: f64 = 0.2;
-const OSCILLATOR_STEP: f64 = OSCILLATOR_SPACE * OSCILLATOR_HZ / FRAME_RATE as f64;
+fn pattern_by_index(index: usize) -> Box<dyn LedPattern> {
+    match index % PATTERN_COUNT {
+        0 => Box::new(Oscillator::new()),
+        1 => Box::new(Solid::new()),
+        2 => Box::new(Rainbow::new()),
+        3 => Box::new(Cylon::new()),
+        _ => Box::new(Twinkle::new()),
+    }
+}
 
 pub struct LedDisplay<'d> {
     driver: Ws2812Esp32Rmt<'d>,
     pixels: u8,
-    hue: u8,
-    sat: u8,
-    val: u8,
     frame: u32,
+    buffer: Vec<RGB8>,
+    pattern_index: usize,
+    pattern: Box<dyn LedPattern>,
 }
 
 impl<'d> LedDisplay<'d> {
@@ -35,35 +43,34 @@ impl<'d> LedDisplay<'d> {
         Ok(Self {
             driver,
             pixels,
-            hue: 0,
-            sat: 255,
-            val: 16,
             frame: 0,
+            buffer: vec![RGB8::new(0, 0, 0); pixels as usize],
+            pattern_index: 0,
+            pattern: pattern_by_index(0),
         })
     }
 
-    pub fn oscillator_value(&self) -> f64 {
-        (self.frame as f64 * OSCILLATOR_STEP).sin()
+    // Swap in an arbitrary pattern implementation, bypassing the fixed rotation - e.g. for a peer
+    // on the ESP-NOW network to push a specific pattern rather than stepping through the rotation.
+    #[allow(dead_code)]
+    pub fn set_pattern(&mut self, pattern: Box<dyn LedPattern>) {
+        self.pattern = pattern;
+    }
+
+    // Advance to the next pattern in the fixed rotation, e.g. in response to a button Click
+    pub fn next_pattern(&mut self) {
+        self.pattern_index = (self.pattern_index + 1) % PATTERN_COUNT;
+        self.pattern = pattern_by_index(self.pattern_index);
     }
 
     pub fn set_hue(&mut self, hue: u8) {
-        self.hue = hue;
+        self.pattern.set_hue(hue);
     }
 
     pub fn render_frame(&mut self) {
-        let oscillator = self.oscillator_value();
-        // wrapping_add_signed is limited to i8
-        // oscillator math should not return a value > +/- 127
-        let h = self.hue.wrapping_add_signed((16f64 * oscillator) as i8);
-        let pixels = (0..self.pixels).map(|i| {
-            hsv2rgb(Hsv {
-                hue: h.wrapping_add(i * 64),
-                sat: self.sat,
-                val: self.val,
-            })
-        });
-        self.driver.write(pixels).unwrap();
-        self.frame += 1;
+        self.pattern.render(self.frame, self.pixels, &mut self.buffer);
+        self.driver.write(self.buffer.iter().copied()).unwrap();
+        self.frame = self.frame.wrapping_add(1);
     }
 }
 
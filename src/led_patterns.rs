@@ -0,0 +1,186 @@
+use smart_leds::hsv::{hsv2rgb, Hsv};
+use smart_leds::RGB8;
+
+const FRAME_RATE: u32 = 60;
+
+// A selectable LED effect. `LedDisplay` holds one behind a `Box<dyn LedPattern>` and renders it
+// every frame; `set_hue` is an optional side channel for patterns that want to react to an
+// external input (e.g. the switch bitmask in main.rs) without baking that wiring into the trait.
+pub trait LedPattern {
+    fn render(&mut self, frame: u32, pixels: u8, out: &mut [RGB8]);
+
+    fn set_hue(&mut self, _hue: u8) {}
+}
+
+// One flat color across every pixel
+pub struct Solid {
+    hue: u8,
+}
+
+impl Solid {
+    pub fn new() -> Self {
+        Self { hue: 0 }
+    }
+}
+
+impl LedPattern for Solid {
+    fn render(&mut self, _frame: u32, _pixels: u8, out: &mut [RGB8]) {
+        let color = hsv2rgb(Hsv {
+            hue: self.hue,
+            sat: 255,
+            val: 16,
+        });
+        out.fill(color);
+    }
+
+    fn set_hue(&mut self, hue: u8) {
+        self.hue = hue;
+    }
+}
+
+// A rainbow spread evenly across the strip, rotating by one hue step per frame
+pub struct Rainbow;
+
+impl Rainbow {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl LedPattern for Rainbow {
+    fn render(&mut self, frame: u32, pixels: u8, out: &mut [RGB8]) {
+        let step = (frame % 256) as u8;
+        let spread = (256 / pixels.max(1) as u32) as u8;
+        for (i, pixel) in out.iter_mut().enumerate() {
+            *pixel = hsv2rgb(Hsv {
+                hue: step.wrapping_add(spread.wrapping_mul(i as u8)),
+                sat: 255,
+                val: 16,
+            });
+        }
+    }
+}
+
+// The original display behaviour: a single hue, slowly swept back and forth by a sine
+// oscillator, spread across the strip with a fixed per-pixel offset
+pub struct Oscillator {
+    hue: u8,
+    sat: u8,
+    val: u8,
+}
+
+impl Oscillator {
+    const SPACE: f64 = std::f64::consts::PI * 2.0;
+    const HZ: f64 = 0.2;
+    const STEP: f64 = Self::SPACE * Self::HZ / FRAME_RATE as f64;
+
+    pub fn new() -> Self {
+        Self {
+            hue: 0,
+            sat: 255,
+            val: 16,
+        }
+    }
+}
+
+impl LedPattern for Oscillator {
+    fn render(&mut self, frame: u32, _pixels: u8, out: &mut [RGB8]) {
+        let oscillator = (frame as f64 * Self::STEP).sin();
+        // wrapping_add_signed is limited to i8; oscillator math should not return a value > +/- 127
+        let h = self.hue.wrapping_add_signed((16f64 * oscillator) as i8);
+        for (i, pixel) in out.iter_mut().enumerate() {
+            *pixel = hsv2rgb(Hsv {
+                hue: h.wrapping_add((i as u8).wrapping_mul(64)),
+                sat: self.sat,
+                val: self.val,
+            });
+        }
+    }
+
+    fn set_hue(&mut self, hue: u8) {
+        self.hue = hue;
+    }
+}
+
+// A single bright pixel that bounces end to end, Larson-scanner style
+pub struct Cylon {
+    hue: u8,
+}
+
+impl Cylon {
+    pub fn new() -> Self {
+        Self { hue: 0 }
+    }
+}
+
+impl LedPattern for Cylon {
+    fn render(&mut self, frame: u32, pixels: u8, out: &mut [RGB8]) {
+        out.fill(RGB8::new(0, 0, 0));
+        if pixels == 0 {
+            return;
+        }
+        if pixels == 1 {
+            out[0] = hsv2rgb(Hsv {
+                hue: self.hue,
+                sat: 255,
+                val: 64,
+            });
+            return;
+        }
+
+        // Bounce back and forth across [0, pixels) with a period of 2*(pixels-1) frames
+        let last = pixels as u32 - 1;
+        let span = last * 2;
+        let offset = frame % span;
+        let position = if offset <= last { offset } else { span - offset };
+        out[position as usize] = hsv2rgb(Hsv {
+            hue: self.hue,
+            sat: 255,
+            val: 64,
+        });
+    }
+
+    fn set_hue(&mut self, hue: u8) {
+        self.hue = hue;
+    }
+}
+
+// Pixels flicker on and off at random, all lit in the same color
+pub struct Twinkle {
+    hue: u8,
+}
+
+impl Twinkle {
+    pub fn new() -> Self {
+        Self { hue: 0 }
+    }
+
+    // Cheap per-pixel pseudo-randomness - no external RNG dependency, just enough variation to
+    // look like twinkling rather than a strict pattern
+    fn lit(frame: u32, pixel: u8) -> bool {
+        let mut x = frame.wrapping_mul(2654435761).wrapping_add(pixel as u32 * 40503);
+        x ^= x >> 13;
+        x = x.wrapping_mul(2246822519);
+        ((x ^ (x >> 16)) & 0b111) == 0
+    }
+}
+
+impl LedPattern for Twinkle {
+    fn render(&mut self, frame: u32, _pixels: u8, out: &mut [RGB8]) {
+        for (i, pixel) in out.iter_mut().enumerate() {
+            *pixel = if Self::lit(frame, i as u8) {
+                hsv2rgb(Hsv {
+                    hue: self.hue,
+                    sat: 255,
+                    val: 32,
+                })
+            } else {
+                RGB8::new(0, 0, 0)
+            };
+        }
+    }
+
+    fn set_hue(&mut self, hue: u8) {
+        self.hue = hue;
+    }
+}
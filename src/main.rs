@@ -5,16 +5,21 @@ use esp_idf_svc::{
     eventloop::EspSystemEventLoop,
     hal::{delay, gpio::IOPin, peripherals::Peripherals, task::block_on},
     log::EspLogger,
+    nvs::EspDefaultNvsPartition,
     sys::{link_patches, EspError},
     timer::EspTaskTimerService,
+    wifi::EspWifi,
 };
 use esp_inputs::{Event as InputEvent, InputManager};
 use log::info;
 use std::time::Duration;
 
+mod esp_now;
 mod events;
 mod led_display;
+mod led_patterns;
 
+use esp_now::EspNowTransport;
 use led_display::{frame_timer, LedDisplay};
 
 // static NETWORK_SSID: &'static str = env!("NETWORK_SSID");
@@ -32,6 +37,16 @@ fn main() -> Result<(), EspError> {
     let sys_loop = EspSystemEventLoop::take()?;
     let peripherals = Peripherals::take()?;
 
+    // Start Wi-Fi (without joining an AP) so ESP-NOW has a radio to broadcast on, then wire up
+    // the transport that shares input and display events across boards
+    let mut wifi = EspWifi::new(
+        peripherals.modem,
+        sys_loop.clone(),
+        Some(EspDefaultNvsPartition::take()?),
+    )?;
+    wifi.start()?;
+    let esp_now = EspNowTransport::new(sys_loop.clone())?;
+
     // Setup input handlers
     let mut inputs = InputManager::new();
     inputs.new_switch(peripherals.pins.gpio5.downgrade(), true)?;
@@ -42,6 +57,8 @@ fn main() -> Result<(), EspError> {
     inputs.new_switch(peripherals.pins.gpio10.downgrade(), true)?;
     inputs.new_switch(peripherals.pins.gpio20.downgrade(), true)?;
     inputs.new_switch(peripherals.pins.gpio21.downgrade(), true)?;
+    // Cycles the LED pattern on a Click, see events::Event::Input handling below
+    inputs.new_button(peripherals.pins.gpio2.downgrade(), true)?;
 
     // Check the inputs via a timer circuit
     let input_timer = {
@@ -49,9 +66,11 @@ fn main() -> Result<(), EspError> {
         let sys_loop = sys_loop.clone();
         timer_service.timer(move || {
             for event in inputs.events() {
-                sys_loop
-                    .post::<events::Event>(&(event.into()), delay::BLOCK)
-                    .unwrap();
+                let event: events::Event = event.into();
+                sys_loop.post::<events::Event>(&event, delay::BLOCK).unwrap();
+                if let Err(e) = esp_now.send(event) {
+                    log::error!("Failed to broadcast event over ESP-NOW: {:?}", e);
+                }
             }
         })?
     };
@@ -77,11 +96,12 @@ fn main() -> Result<(), EspError> {
                     display.render_frame();
                 }
                 events::Event::Input(e) => {
-                    bits = match e {
-                        InputEvent::On(gpio) => bits | gpio_to_bit_mask(gpio),
-                        InputEvent::Off(gpio) => bits & !gpio_to_bit_mask(gpio),
-                        _ => bits,
-                    };
+                    match e {
+                        InputEvent::On(gpio) => bits |= gpio_to_bit_mask(gpio),
+                        InputEvent::Off(gpio) => bits &= !gpio_to_bit_mask(gpio),
+                        InputEvent::Click(_) => display.next_pattern(),
+                        _ => {}
+                    }
 
                     display.set_hue(bits);
 